@@ -0,0 +1,160 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use uv_fs::Simplified;
+use uv_tool::find_executable_directory;
+
+use crate::commands::ExitStatus;
+use crate::printer::Printer;
+
+/// A shell whose startup files we know how to extend with a `PATH` entry.
+#[derive(Debug, Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl Shell {
+    /// Determine the current user's shell from the environment.
+    fn from_env() -> Option<Self> {
+        if std::env::var_os("PSModulePath").is_some() {
+            return Some(Self::PowerShell);
+        }
+
+        let shell = std::env::var("SHELL").ok()?;
+        let name = shell.rsplit('/').next().unwrap_or(&shell);
+        match name {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+
+    /// The rc or profile file this shell reads on startup.
+    fn config_file(self) -> Option<PathBuf> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        match self {
+            Self::Bash => home.map(|home| home.join(".bashrc")),
+            Self::Zsh => home.map(|home| home.join(".zshrc")),
+            Self::Fish => home.map(|home| home.join(".config/fish/config.fish")),
+            Self::PowerShell => powershell_profile(),
+        }
+    }
+
+    /// The line to append to make `directory` available on `PATH`.
+    fn snippet(self, directory: &std::path::Path) -> String {
+        let directory = directory.simplified_display();
+        match self {
+            Self::Bash | Self::Zsh => format!("export PATH=\"{directory}:$PATH\""),
+            Self::Fish => format!("fish_add_path \"{directory}\""),
+            Self::PowerShell => format!("$env:PATH = \"{directory};$env:PATH\""),
+        }
+    }
+}
+
+/// Determine PowerShell's `$PROFILE` path.
+///
+/// `$PROFILE` is a session-scoped automatic variable set by the PowerShell host, not an inherited
+/// environment variable, so a spawned child process (like `uv`) never sees it in its environment.
+/// Ask a PowerShell process for its value instead, preferring `pwsh` (PowerShell 7+, cross
+/// platform) and falling back to Windows PowerShell's `powershell.exe`.
+fn powershell_profile() -> Option<PathBuf> {
+    for executable in ["pwsh", "powershell"] {
+        let Ok(output) = std::process::Command::new(executable)
+            .args(["-NoProfile", "-Command", "$PROFILE"])
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        let Ok(path) = String::from_utf8(output.stdout) else {
+            continue;
+        };
+        let path = path.trim();
+        if !path.is_empty() {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// A marker we prefix our managed snippet with, so re-running `ensurepath` is a no-op.
+///
+/// The executable directory is embedded in the marker itself (e.g.
+/// `# added by uv (/home/user/.local/bin)`), rather than a bare constant, so that a change in
+/// `find_executable_directory()` is detected as "not yet configured" instead of being skipped
+/// because some *other* directory was already added.
+fn marker(directory: &std::path::Path) -> String {
+    format!("# added by uv ({})", directory.simplified_display())
+}
+
+/// Ensure that the `uv tool` executable directory is on the user's `PATH`.
+pub(crate) async fn ensurepath(printer: Printer) -> Result<ExitStatus> {
+    let executable_directory = find_executable_directory()?;
+
+    let Some(shell) = Shell::from_env() else {
+        writeln!(
+            printer.stderr(),
+            "Could not detect your shell from the environment; add the following to your shell's \
+             configuration file:\n\n    export PATH=\"{}:$PATH\"",
+            executable_directory.simplified_display()
+        )?;
+        return Ok(ExitStatus::Failure);
+    };
+
+    let Some(config_file) = shell.config_file() else {
+        writeln!(
+            printer.stderr(),
+            "Could not determine your shell's configuration file; add the following manually:\n\n    {}",
+            shell.snippet(&executable_directory)
+        )?;
+        return Ok(ExitStatus::Failure);
+    };
+
+    let marker = marker(&executable_directory);
+    let snippet = format!("\n{marker}\n{}\n", shell.snippet(&executable_directory));
+
+    let existing = fs_err::read_to_string(&config_file).unwrap_or_default();
+    if existing.contains(&marker) {
+        writeln!(
+            printer.stderr(),
+            "`{}` is already configured to update your PATH",
+            config_file.simplified_display()
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    if let Some(parent) = config_file.parent() {
+        fs_err::create_dir_all(parent).context("Failed to create shell configuration directory")?;
+    }
+
+    fs_err::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config_file)
+        .and_then(|mut file| {
+            use std::io::Write as _;
+            file.write_all(snippet.as_bytes())
+        })
+        .with_context(|| {
+            format!(
+                "Failed to update `{}`",
+                config_file.simplified_display()
+            )
+        })?;
+
+    writeln!(
+        printer.stderr(),
+        "Updated `{}`; restart your shell for it to take effect",
+        config_file.simplified_display()
+    )?;
+
+    Ok(ExitStatus::Success)
+}
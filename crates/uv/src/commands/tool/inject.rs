@@ -0,0 +1,163 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+use pypi_types::Requirement;
+use uv_cache::Cache;
+use uv_client::Connectivity;
+use uv_configuration::{Concurrency, PreviewMode};
+use uv_normalize::PackageName;
+use uv_requirements::RequirementsSpecification;
+use uv_tool::{InstalledTools, Tool};
+use uv_warnings::warn_user_once;
+
+use crate::commands::project::update_environment;
+use crate::commands::tool::common::resolve_requirements;
+use crate::commands::{ExitStatus, SharedState};
+use crate::printer::Printer;
+use crate::settings::ResolverInstallerSettings;
+
+/// Inject a dependency into the environment of an installed tool.
+pub(crate) async fn inject(
+    name: String,
+    with: Vec<String>,
+    settings: ResolverInstallerSettings,
+    preview: PreviewMode,
+    connectivity: Connectivity,
+    concurrency: Concurrency,
+    native_tls: bool,
+    cache: &Cache,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    if preview.is_disabled() {
+        warn_user_once!("`uv tool inject` is experimental and may change without warning.");
+    }
+
+    let Ok(name) = PackageName::from_str(&name) else {
+        bail!("Invalid tool name: `{name}`")
+    };
+
+    let installed_tools = InstalledTools::from_settings()?;
+    let Some(tool_receipt) = installed_tools.get_tool_receipt(&name)? else {
+        bail!("Tool `{name}` is not installed")
+    };
+    let Some(environment) = installed_tools.get_environment(&name, cache)? else {
+        bail!(
+            "Tool `{name}` is missing its environment; reinstall it with `uv tool install --force {name}`"
+        )
+    };
+
+    let interpreter = environment.interpreter();
+    let state = SharedState::default();
+
+    let incoming = resolve_requirements(
+        with.iter().map(String::as_str),
+        interpreter,
+        &settings,
+        &state,
+        preview,
+        connectivity,
+        concurrency,
+        native_tls,
+        cache,
+        printer,
+    )
+    .await?;
+
+    if incoming.is_empty() {
+        bail!("Expected at least one requirement to inject");
+    }
+
+    check_no_self_target(&name, incoming.iter().map(|requirement| &requirement.name))?;
+
+    // Merge the incoming requirements into the existing set, with the incoming requirements
+    // taking precedence over any existing requirement for the same package.
+    let mut requirements = tool_receipt
+        .requirements()
+        .iter()
+        .cloned()
+        .map(Requirement::from)
+        .collect::<Vec<_>>();
+    for requirement in incoming {
+        requirements.retain(|existing| existing.name != requirement.name);
+        requirements.push(requirement);
+    }
+
+    let spec = RequirementsSpecification::from_requirements(requirements.clone());
+
+    update_environment(
+        environment,
+        spec,
+        &settings,
+        &state,
+        preview,
+        connectivity,
+        concurrency,
+        native_tls,
+        cache,
+        printer,
+    )
+    .await?;
+
+    let installed_tools = installed_tools.init()?;
+    let tool = Tool::new(
+        requirements
+            .into_iter()
+            .map(pep508_rs::Requirement::from)
+            .collect(),
+        tool_receipt.python().map(ToString::to_string),
+        tool_receipt.entrypoints().iter().cloned(),
+    );
+    installed_tools.add_tool_receipt(&name, tool)?;
+
+    writeln!(printer.stderr(), "Injected into `{name}`")?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Reject injecting a requirement for `name` itself.
+///
+/// `uv tool inject black black==23.1` would otherwise silently rewrite the tool's own pinned
+/// version, which is what `uv tool install --force` is for.
+fn check_no_self_target<'a>(
+    name: &PackageName,
+    incoming: impl Iterator<Item = &'a PackageName>,
+) -> Result<()> {
+    for requirement_name in incoming {
+        if requirement_name == name {
+            bail!(
+                "`{name}` is the target tool; use `uv tool install --force {name}` to change its \
+                 own version"
+            )
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_requirement_for_the_tool_itself() {
+        let name = PackageName::from_str("black").unwrap();
+        let incoming = vec![PackageName::from_str("black").unwrap()];
+
+        let err = check_no_self_target(&name, incoming.iter())
+            .expect_err("injecting the tool's own package should be rejected");
+        assert!(err.to_string().contains("is the target tool"));
+    }
+
+    #[test]
+    fn allows_unrelated_requirements() {
+        let name = PackageName::from_str("black").unwrap();
+        let incoming = vec![
+            PackageName::from_str("black-plugin").unwrap(),
+            PackageName::from_str("isort").unwrap(),
+        ];
+
+        check_no_self_target(&name, incoming.iter())
+            .expect("requirements for other packages should be allowed");
+    }
+}
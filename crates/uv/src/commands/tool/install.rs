@@ -1,7 +1,6 @@
 use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::fmt::Write;
-use std::str::FromStr;
 
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
@@ -9,19 +8,20 @@ use tracing::debug;
 
 use distribution_types::Name;
 use pypi_types::Requirement;
-use uv_cache::Cache;
+use uv_cache::{Cache, CacheBucket};
 use uv_client::{BaseClientBuilder, Connectivity};
 use uv_configuration::{Concurrency, PreviewMode};
 #[cfg(unix)]
 use uv_fs::replace_symlink;
 use uv_fs::Simplified;
 use uv_installer::SitePackages;
-use uv_normalize::PackageName;
 use uv_python::{
-    EnvironmentPreference, PythonFetch, PythonInstallation, PythonPreference, PythonRequest,
+    EnvironmentPreference, PythonEnvironment, PythonFetch, PythonInstallation, PythonPreference,
+    PythonRequest,
 };
 use uv_requirements::RequirementsSpecification;
 use uv_tool::{entrypoint_paths, find_executable_directory, InstalledTools, Tool, ToolEntrypoint};
+use uv_virtualenv::create_venv;
 use uv_warnings::warn_user_once;
 
 use crate::commands::project::{resolve_environment, sync_environment, update_environment};
@@ -37,6 +37,7 @@ pub(crate) async fn install(
     from: Option<String>,
     python: Option<String>,
     with: Vec<String>,
+    include_deps: bool,
     force: bool,
     settings: ResolverInstallerSettings,
     preview: PreviewMode,
@@ -77,14 +78,12 @@ pub(crate) async fn install(
     // Initialize any shared state.
     let state = SharedState::default();
 
-    // Resolve the `from` requirement.
+    // Resolve the `from` requirement. The positional `package` argument accepts the same grammar
+    // as `--from`: a PEP 508 requirement, a local path, a wheel or sdist, or a direct URL (e.g. a
+    // VCS reference), optionally `-e`/editable. We resolve both and derive their names from the
+    // resulting distribution rather than requiring `package` to parse as a bare package name,
+    // since it may not be one (e.g. `uv tool install ./my-cli`).
     let from = if let Some(from) = from {
-        // Parse the positional name. If the user provided more than a package name, it's an error
-        // (e.g., `uv install foo==1.0 --from foo`).
-        let Ok(package) = PackageName::from_str(&package) else {
-            bail!("Package requirement `{from}` provided with `--from` conflicts with install request `{package}`")
-        };
-
         let from_requirement = resolve_requirements(
             std::iter::once(from.as_str()),
             &interpreter,
@@ -101,13 +100,29 @@ pub(crate) async fn install(
         .pop()
         .unwrap();
 
-        // Check if the positional name conflicts with `--from`.
-        if from_requirement.name != package {
+        let package_requirement = resolve_requirements(
+            std::iter::once(package.as_str()),
+            &interpreter,
+            &settings,
+            &state,
+            preview,
+            connectivity,
+            concurrency,
+            native_tls,
+            cache,
+            printer,
+        )
+        .await?
+        .pop()
+        .unwrap();
+
+        // Check if the positional package conflicts with `--from`.
+        if from_requirement.name != package_requirement.name {
             // Determine if it's an entirely different package (e.g., `uv install foo --from bar`).
             bail!(
                 "Package name `{}` provided with `--from` does not match install request `{}`",
                 from_requirement.name,
-                package
+                package_requirement.name
             );
         }
 
@@ -198,18 +213,50 @@ pub(crate) async fn install(
     // entrypoints later on, and the tool _doesn't_ exist, we'll avoid removing the external tool's
     // entrypoints (without `--force`).
     let reinstall_entry_points = existing_tool_receipt.is_some();
+    let allow_entry_point_overwrite = force || reinstall_entry_points;
+
+    // Find a suitable path to install into. This doesn't depend on the environment, so we can
+    // resolve it (and validate entry points against it) before touching any existing install.
+    let executable_directory = find_executable_directory()?;
+    fs_err::create_dir_all(&executable_directory)
+        .context("Failed to create executable directory")?;
+
+    warn_if_not_on_path(&executable_directory);
+
+    debug!(
+        "Installing tool entry points into {}",
+        executable_directory.user_display()
+    );
 
     // Resolve the requirements.
     let state = SharedState::default();
     let spec = RequirementsSpecification::from_requirements(requirements.clone());
 
-    // TODO(zanieb): Build the environment in the cache directory then copy into the tool directory.
-    // This lets us confirm the environment is valid before removing an existing install. However,
-    // entrypoints always contain an absolute path to the relevant Python interpreter, which would
-    // be invalidated by moving the environment.
-    let environment = if let Some(environment) = existing_environment {
-        update_environment(
-            environment,
+    // Whether the environment we end up with was just built or updated in the cache, and so its
+    // entry points still embed the staging interpreter path rather than their final one.
+    let mut needs_shebang_rewrite = false;
+
+    let (environment, target_entry_points) = if let Some(environment) = existing_environment {
+        // As with a fresh install, stage the update in the cache directory rather than syncing
+        // the live tool environment in place. If resolution or sync fails partway through, the
+        // existing install is untouched instead of being left half-upgraded.
+        let staging_path = cache
+            .bucket(CacheBucket::Environments)
+            .join(format!("tool-{}-update", from.name));
+        if staging_path.exists() {
+            fs_err::remove_dir_all(&staging_path)?;
+        }
+        fs_err::create_dir_all(
+            staging_path
+                .parent()
+                .context("Cache directory has no parent")?,
+        )?;
+        copy_dir_all(environment.root(), &staging_path)
+            .context("Failed to stage existing tool environment for update")?;
+        let staged_environment = PythonEnvironment::from_root(&staging_path, cache)?;
+
+        let staged_environment = update_environment(
+            staged_environment,
             spec,
             &settings,
             &state,
@@ -220,7 +267,38 @@ pub(crate) async fn install(
             cache,
             printer,
         )
-        .await?
+        .await?;
+
+        // Validate the staged update *before* we touch the existing install: confirm it still
+        // produces at least one entry point, and that it wouldn't collide with an entry point we
+        // aren't allowed to overwrite. If either check fails, the staging directory is left in the
+        // cache (it's cleared on the next attempt) and the live tool environment is never touched.
+        let staged_target_entry_points = resolve_target_entry_points(
+            &staged_environment,
+            &from.name,
+            include_deps,
+            &executable_directory,
+        )?;
+        validate_target_entry_points(
+            &staged_target_entry_points,
+            &from.name,
+            allow_entry_point_overwrite,
+        )?;
+
+        // Validation passed; it's now safe to replace the existing install with the staged one.
+        needs_shebang_rewrite = true;
+        let environment = installed_tools.install_environment(&from.name, staged_environment)?;
+
+        // Re-resolve against the now-final environment: moving it out of the cache changed its
+        // root, and with it every entry point's absolute path.
+        let target_entry_points = resolve_target_entry_points(
+            &environment,
+            &from.name,
+            include_deps,
+            &executable_directory,
+        )?;
+
+        (environment, target_entry_points)
     } else {
         // If we're creating a new environment, ensure that we can resolve the requirements prior
         // to removing any existing tools.
@@ -238,11 +316,33 @@ pub(crate) async fn install(
         )
         .await?;
 
-        let environment = installed_tools.create_environment(&from.name, interpreter)?;
-
-        // Sync the environment with the resolved requirements.
-        sync_environment(
-            environment,
+        // Build the environment in the cache directory rather than directly in the tool
+        // directory. This lets us validate the resolution and entry points before we remove any
+        // existing install; if `sync_environment` fails, or we find an entry point collision
+        // below, the (untouched) existing install is left in place.
+        let staging_path = cache
+            .bucket(CacheBucket::Environments)
+            .join(format!("tool-{}", from.name));
+        if staging_path.exists() {
+            fs_err::remove_dir_all(&staging_path)?;
+        }
+        fs_err::create_dir_all(
+            staging_path
+                .parent()
+                .context("Cache directory has no parent")?,
+        )?;
+        let staged_environment = create_venv(
+            &staging_path,
+            interpreter,
+            uv_virtualenv::Prompt::None,
+            false,
+            false,
+            false,
+        )?;
+
+        // Sync the staged environment with the resolved requirements.
+        let staged_environment = sync_environment(
+            staged_environment,
             &resolution.into(),
             settings.as_ref().into(),
             &state,
@@ -253,87 +353,86 @@ pub(crate) async fn install(
             cache,
             printer,
         )
-        .await?
-    };
+        .await?;
 
-    let site_packages = SitePackages::from_environment(&environment)?;
-    let installed = site_packages.get_packages(&from.name);
-    let Some(installed_dist) = installed.first().copied() else {
-        bail!("Expected at least one requirement")
+        // Validate the staged environment *before* we touch the existing install: confirm it
+        // produces at least one entry point, and that installing it wouldn't collide with an
+        // entry point we're not allowed to overwrite. If either check fails, bail out here; the
+        // staging directory is the only thing that exists so far, and the previous install (if
+        // any) is untouched.
+        let staged_target_entry_points = resolve_target_entry_points(
+            &staged_environment,
+            &from.name,
+            include_deps,
+            &executable_directory,
+        )?;
+        validate_target_entry_points(
+            &staged_target_entry_points,
+            &from.name,
+            allow_entry_point_overwrite,
+        )?;
+
+        // Validation passed; it's now safe to replace any existing install with the staged
+        // environment.
+        needs_shebang_rewrite = true;
+        let environment = installed_tools.install_environment(&from.name, staged_environment)?;
+
+        // Re-resolve against the now-final environment: moving it out of the cache changed its
+        // root, and with it every entry point's absolute path.
+        let target_entry_points = resolve_target_entry_points(
+            &environment,
+            &from.name,
+            include_deps,
+            &executable_directory,
+        )?;
+
+        (environment, target_entry_points)
     };
 
-    // Find a suitable path to install into
-    // TODO(zanieb): Warn if this directory is not on the PATH
-    let executable_directory = find_executable_directory()?;
-    fs_err::create_dir_all(&executable_directory)
-        .context("Failed to create executable directory")?;
-
-    debug!(
-        "Installing tool entry points into {}",
-        executable_directory.user_display()
-    );
-
-    let entry_points = entrypoint_paths(
-        &environment,
-        installed_dist.name(),
-        installed_dist.version(),
-    )?;
-
-    // Determine the entry points targets
-    // Use a sorted collection for deterministic output
-    let target_entry_points = entry_points
-        .into_iter()
-        .map(|(name, source_path)| {
-            let target_path = executable_directory.join(
-                source_path
-                    .file_name()
-                    .map(std::borrow::ToOwned::to_owned)
-                    .unwrap_or_else(|| OsString::from(name.clone())),
-            );
-            (name, source_path, target_path)
-        })
-        .collect::<BTreeSet<_>>();
-
-    if target_entry_points.is_empty() {
-        // Clean up the environment we just created
-        installed_tools.remove_environment(&from.name)?;
+    // The entry points we just generated embed an absolute path to the interpreter that built
+    // them. If we built the environment in the cache and then moved it into place, that path
+    // points at the now-deleted staging directory; rewrite it to the environment's final
+    // interpreter before we link the scripts.
+    if needs_shebang_rewrite {
+        for (_, source_path, _, _) in &target_entry_points {
+            rewrite_shebang(source_path, environment.interpreter().sys_executable())?;
+        }
+    }
 
-        bail!("No entry points found for tool `{}`", from.name);
+    // Note we use `allow_entry_point_overwrite` here instead of `reinstall`; requesting reinstall
+    // will _not_ remove existing entry points when they are not managed by uv. We already
+    // confirmed above that it's safe to do this.
+    if allow_entry_point_overwrite {
+        for (name, _, target_path, _) in &target_entry_points {
+            if target_path.exists() {
+                debug!("Removing existing entry point `{name}`");
+                fs_err::remove_file(target_path)?;
+            }
+        }
     }
 
-    // Check if they exist, before installing
-    let mut existing_entry_points = target_entry_points
-        .iter()
-        .filter(|(_, _, target_path)| target_path.exists())
-        .peekable();
-
-    // Note we use `reinstall_entry_points` here instead of `reinstall`; requesting reinstall
-    // will _not_ remove existing entry points when they are not managed by uv.
-    if force || reinstall_entry_points {
-        for (name, _, target) in existing_entry_points {
-            debug!("Removing existing entry point `{name}`");
-            fs_err::remove_file(target)?;
+    // If we're replacing a previous install of this tool, remove any entry point that the
+    // previous receipt recorded but that isn't part of this install (e.g., the tool dropped a
+    // console script in a newer version, or a reinstall without `--include-deps` no longer
+    // exposes entry points from a dependency). Without this, those targets would keep pointing
+    // at an environment we're about to discard.
+    if let Some(existing_tool_receipt) = existing_tool_receipt.as_ref() {
+        let target_paths = target_entry_points
+            .iter()
+            .map(|(_, _, target_path, _)| target_path.as_path())
+            .collect::<BTreeSet<_>>();
+        for entry_point in existing_tool_receipt.entrypoints() {
+            if target_paths.contains(entry_point.install_path()) {
+                continue;
+            }
+            debug!("Removing orphaned entry point `{}`", entry_point.name());
+            if entry_point.install_path().exists() {
+                fs_err::remove_file(entry_point.install_path())?;
+            }
         }
-    } else if existing_entry_points.peek().is_some() {
-        // Clean up the environment we just created
-        installed_tools.remove_environment(&from.name)?;
-
-        let existing_entry_points = existing_entry_points
-            // SAFETY: We know the target has a filename because we just constructed it above
-            .map(|(_, _, target)| target.file_name().unwrap().to_string_lossy())
-            .collect::<Vec<_>>();
-        let (s, exists) = if existing_entry_points.len() == 1 {
-            ("", "exists")
-        } else {
-            ("s", "exist")
-        };
-        bail!(
-            "Entry point{s} for tool already {exists}: {} (use `--force` to overwrite)",
-            existing_entry_points.iter().join(", ")
-        )
     }
 
-    for (name, source_path, target_path) in &target_entry_points {
+    for (name, source_path, target_path, _) in &target_entry_points {
         debug!("Installing `{name}`");
         #[cfg(unix)]
         replace_symlink(source_path, target_path).context("Failed to install entrypoint")?;
@@ -346,7 +445,7 @@ pub(crate) async fn install(
         "Installed: {}",
         target_entry_points
             .iter()
-            .map(|(name, _, _)| name)
+            .map(|(name, _, _, _)| name)
             .join(", ")
     )?;
 
@@ -360,9 +459,359 @@ pub(crate) async fn install(
         python,
         target_entry_points
             .into_iter()
-            .map(|(name, _, target_path)| ToolEntrypoint::new(name, target_path)),
+            .map(|(name, _, target_path, package)| ToolEntrypoint::new(name, target_path, package)),
     );
     installed_tools.add_tool_receipt(&from.name, tool)?;
 
     Ok(ExitStatus::Success)
 }
+
+/// Resolve the console-script entry points to expose for a tool in `environment`.
+///
+/// By default, only the entry points of `from_name`'s own distribution are included. With
+/// `include_deps`, the entry points of every distribution installed in the environment are
+/// included as well (e.g., a linter plugin installed as a dependency of the linter itself).
+fn resolve_target_entry_points(
+    environment: &PythonEnvironment,
+    from_name: &uv_normalize::PackageName,
+    include_deps: bool,
+    executable_directory: &std::path::Path,
+) -> Result<BTreeSet<(String, std::path::PathBuf, std::path::PathBuf, uv_normalize::PackageName)>> {
+    let site_packages = SitePackages::from_environment(environment)?;
+    let installed = site_packages.get_packages(from_name);
+    let Some(installed_dist) = installed.first().copied() else {
+        bail!("Expected at least one requirement")
+    };
+
+    let entry_point_dists = if include_deps {
+        site_packages.iter().collect::<Vec<_>>()
+    } else {
+        vec![installed_dist]
+    };
+
+    let entry_points = entry_point_dists
+        .into_iter()
+        .map(|dist| {
+            entrypoint_paths(environment, dist.name(), dist.version())
+                .map(|entry_points| (dist.name().clone(), entry_points))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flat_map(|(package, entry_points)| {
+            entry_points
+                .into_iter()
+                .map(move |(name, source_path)| (name, source_path, package.clone()))
+        })
+        .map(|(name, source_path, package)| {
+            let target_path = executable_directory.join(
+                source_path
+                    .file_name()
+                    .map(std::borrow::ToOwned::to_owned)
+                    .unwrap_or_else(|| OsString::from(name.clone())),
+            );
+            (name, source_path, target_path, package)
+        });
+
+    dedup_entry_points(entry_points.collect())
+}
+
+/// De-duplicate resolved entry points by `(name, target_path)`, regardless of which package
+/// produced them.
+///
+/// Two distributions in the same environment (e.g., the tool and one of its dependencies) can
+/// expose a script with the same name. Rather than linking both to the same `target_path` and
+/// recording two `ToolEntrypoint`s for it in the receipt (which would corrupt `uv tool
+/// list`/`uninstall` cleanup), collapse duplicates produced by the same package and fail if two
+/// different packages actually collide.
+fn dedup_entry_points(
+    entry_points: Vec<(
+        String,
+        std::path::PathBuf,
+        std::path::PathBuf,
+        uv_normalize::PackageName,
+    )>,
+) -> Result<BTreeSet<(String, std::path::PathBuf, std::path::PathBuf, uv_normalize::PackageName)>>
+{
+    let mut by_target = std::collections::BTreeMap::new();
+    for (name, source_path, target_path, package) in entry_points {
+        match by_target.entry((name, target_path)) {
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                entry.insert((source_path, package));
+            }
+            std::collections::btree_map::Entry::Occupied(entry) => {
+                let ((existing_name, _), (_, existing_package)) = (entry.key(), entry.get());
+                if *existing_package != package {
+                    bail!(
+                        "Entry point `{existing_name}` is provided by both `{existing_package}` \
+                         and `{package}`; use `--include-deps` only when their entry points don't \
+                         collide",
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(by_target
+        .into_iter()
+        .map(|((name, target_path), (source_path, package))| {
+            (name, source_path, target_path, package)
+        })
+        .collect::<BTreeSet<_>>())
+}
+
+/// Validate a set of resolved target entry points before they're linked into place.
+///
+/// Fails if there are no entry points at all, or if an entry point would overwrite a file we
+/// don't have permission to overwrite (i.e., `allow_overwrite` is `false` and the target already
+/// exists on disk).
+fn validate_target_entry_points(
+    target_entry_points: &BTreeSet<(String, std::path::PathBuf, std::path::PathBuf, uv_normalize::PackageName)>,
+    from_name: &uv_normalize::PackageName,
+    allow_overwrite: bool,
+) -> Result<()> {
+    if target_entry_points.is_empty() {
+        bail!("No entry points found for tool `{from_name}`");
+    }
+
+    if allow_overwrite {
+        return Ok(());
+    }
+
+    let existing_entry_points = target_entry_points
+        .iter()
+        .filter(|(_, _, target_path, _)| target_path.exists())
+        // SAFETY: We know the target has a filename because we just constructed it above
+        .map(|(_, _, target_path, _)| target_path.file_name().unwrap().to_string_lossy())
+        .collect::<Vec<_>>();
+
+    if existing_entry_points.is_empty() {
+        return Ok(());
+    }
+
+    let (s, exists) = if existing_entry_points.len() == 1 {
+        ("", "exists")
+    } else {
+        ("s", "exist")
+    };
+    bail!(
+        "Entry point{s} for tool already {exists}: {} (use `--force` to overwrite)",
+        existing_entry_points.iter().join(", ")
+    )
+}
+
+/// Recursively copy the contents of `src` into `dst`, creating `dst` if it does not exist.
+fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
+    fs_err::create_dir_all(dst)?;
+    for entry in fs_err::read_dir(src)? {
+        let entry = entry?;
+        let dst = dst.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &dst)?;
+        } else if file_type.is_symlink() {
+            let target = fs_err::read_link(entry.path())?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(target, &dst)?;
+            #[cfg(windows)]
+            fs_err::copy(entry.path(), &dst)?;
+        } else {
+            fs_err::copy(entry.path(), &dst)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite the shebang of a console script entry point to point at `interpreter`.
+///
+/// Entry points generated by the installer embed an absolute path to the interpreter used to
+/// build the environment. When an environment is built in a staging directory and then moved,
+/// that path no longer resolves, so it must be rewritten to the environment's final interpreter.
+#[cfg(unix)]
+fn rewrite_shebang(script: &std::path::Path, interpreter: &std::path::Path) -> Result<()> {
+    // Read raw bytes rather than `read_to_string`: a native-binary launcher is not valid UTF-8,
+    // and should be skipped as "nothing to rewrite" rather than fail the whole install.
+    let contents = fs_err::read(script)?;
+    if !contents.starts_with(b"#!") {
+        // Not a text launcher (e.g., a native binary); nothing to rewrite.
+        return Ok(());
+    }
+    let Some(end_of_line) = contents.iter().position(|&byte| byte == b'\n') else {
+        return Ok(());
+    };
+
+    let interpreter = interpreter.simplified_display().to_string();
+
+    // The kernel enforces a hard limit on the length of a shebang line (it reads it into a
+    // 128-byte buffer whose last byte is reserved, so the line, including the leading `#!` and
+    // the trailing newline, must fit in 127 bytes); a longer interpreter path, such as a
+    // deeply nested virtualenv, would otherwise be silently truncated and break the script at
+    // launch. Past that limit, fall back to the same `/bin/sh` polyglot trampoline that
+    // setuptools/pip use for console scripts: the kernel runs `/bin/sh` on the shebang, which
+    // `exec`s the real interpreter on the script, while Python sees the trampoline lines as an
+    // inert string literal and ignores them.
+    const SHEBANG_LIMIT: usize = 127;
+    let rewritten = if 2 + interpreter.len() + 1 > SHEBANG_LIMIT {
+        let mut rewritten = Vec::with_capacity(contents.len());
+        rewritten.extend_from_slice(b"#!/bin/sh\n");
+        rewritten.extend_from_slice(
+            format!("'''exec' \"{interpreter}\" \"$0\" \"$@\"\n' '''\n").as_bytes(),
+        );
+        rewritten.extend_from_slice(&contents[end_of_line + 1..]);
+        rewritten
+    } else {
+        let mut rewritten = Vec::with_capacity(contents.len());
+        rewritten.extend_from_slice(b"#!");
+        rewritten.extend_from_slice(interpreter.as_bytes());
+        rewritten.extend_from_slice(&contents[end_of_line..]);
+        rewritten
+    };
+
+    fs_err::write(script, rewritten).context("Failed to rewrite entrypoint shebang")?;
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn rewrite_shebang(_script: &std::path::Path, _interpreter: &std::path::Path) -> Result<()> {
+    // Windows launchers resolve the interpreter via an embedded `._pth`-style config rather than
+    // a shebang line, and are regenerated against the final interpreter at creation time.
+    Ok(())
+}
+
+/// Warn the user if `directory` is not on their `PATH`, with the export line needed to add it.
+///
+/// This is the most common cause of "installed but command not found": `uv tool install` worked,
+/// but the shell that ran it doesn't know to look in `executable_directory`.
+pub(crate) fn warn_if_not_on_path(directory: &std::path::Path) {
+    if std::env::var_os("PATH")
+        .as_ref()
+        .map(std::env::split_paths)
+        .is_some_and(|mut paths| paths.any(|path| path == directory))
+    {
+        return;
+    }
+
+    #[cfg(unix)]
+    let export_line = format!("export PATH=\"{}:$PATH\"", directory.simplified_display());
+    #[cfg(windows)]
+    let export_line = format!("$env:PATH = \"{};$env:PATH\"", directory.simplified_display());
+
+    warn_user_once!(
+        "`{}` is not on your PATH. To use installed tools, run `uv tool ensurepath` or add the \
+         directory to your PATH manually:\n\n    {export_line}",
+        directory.simplified_display(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use uv_normalize::PackageName;
+
+    use super::*;
+
+    fn entry_point(
+        name: &str,
+        target_path: &str,
+        package: &str,
+    ) -> (String, std::path::PathBuf, std::path::PathBuf, PackageName) {
+        (
+            name.to_string(),
+            std::path::PathBuf::from(format!("/env/bin/{name}")),
+            std::path::PathBuf::from(target_path),
+            PackageName::from_str(package).unwrap(),
+        )
+    }
+
+    #[test]
+    fn dedup_entry_points_collapses_duplicates_from_the_same_package() {
+        let entry_points = vec![
+            entry_point("black", "/usr/bin/black", "black"),
+            entry_point("black", "/usr/bin/black", "black"),
+        ];
+
+        let deduped = dedup_entry_points(entry_points).unwrap();
+        assert_eq!(deduped.len(), 1);
+    }
+
+    #[test]
+    fn dedup_entry_points_rejects_collisions_across_packages() {
+        // Two different packages (the tool and one of its `--include-deps` dependencies) both
+        // expose a `black` console script.
+        let entry_points = vec![
+            entry_point("black", "/usr/bin/black", "black"),
+            entry_point("black", "/usr/bin/black", "black-plugin"),
+        ];
+
+        let err = dedup_entry_points(entry_points)
+            .expect_err("colliding entry points from different packages should be rejected");
+        assert!(err.to_string().contains("is provided by both"));
+    }
+
+    #[test]
+    fn validate_target_entry_points_rejects_empty_set() {
+        let name = PackageName::from_str("black").unwrap();
+        let err = validate_target_entry_points(&BTreeSet::new(), &name, false)
+            .expect_err("an empty entry point set should be rejected");
+        assert!(err.to_string().contains("No entry points found"));
+    }
+
+    #[test]
+    fn validate_target_entry_points_allows_overwrite_when_permitted() {
+        let name = PackageName::from_str("black").unwrap();
+        let tempdir = tempfile::tempdir().unwrap();
+        let target_path = tempdir.path().join("black");
+        fs_err::write(&target_path, b"").unwrap();
+
+        let target_entry_points = BTreeSet::from([entry_point(
+            "black",
+            target_path.to_str().unwrap(),
+            "black",
+        )]);
+
+        // Without permission to overwrite, the existing file on disk is a conflict.
+        validate_target_entry_points(&target_entry_points, &name, false)
+            .expect_err("an existing entry point should be rejected without --force");
+
+        // With permission to overwrite, it's allowed.
+        validate_target_entry_points(&target_entry_points, &name, true)
+            .expect("an existing entry point should be allowed with --force");
+    }
+
+    #[test]
+    fn rewrite_shebang_rewrites_short_interpreter_paths_in_place() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let script = tempdir.path().join("black");
+        fs_err::write(&script, b"#!/old/env/bin/python3\nimport black\nblack.main()\n").unwrap();
+
+        rewrite_shebang(&script, std::path::Path::new("/usr/bin/python3")).unwrap();
+
+        let contents = fs_err::read_to_string(&script).unwrap();
+        assert_eq!(
+            contents,
+            "#!/usr/bin/python3\nimport black\nblack.main()\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_shebang_falls_back_to_a_trampoline_for_long_interpreter_paths() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let script = tempdir.path().join("black");
+        fs_err::write(&script, b"#!/old/env/bin/python3\nimport black\nblack.main()\n").unwrap();
+
+        let interpreter = format!("/{}/bin/python3", "a".repeat(200));
+        rewrite_shebang(&script, std::path::Path::new(&interpreter)).unwrap();
+
+        let contents = fs_err::read_to_string(&script).unwrap();
+        let mut lines = contents.lines();
+        assert_eq!(lines.next().unwrap(), "#!/bin/sh");
+        assert_eq!(
+            lines.next().unwrap(),
+            format!("'''exec' \"{interpreter}\" \"$0\" \"$@\"")
+        );
+        assert_eq!(lines.next().unwrap(), "' '''");
+        assert_eq!(lines.next().unwrap(), "import black");
+        assert_eq!(lines.next().unwrap(), "black.main()");
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use itertools::Itertools;
+use tracing::debug;
+
+use pypi_types::Requirement;
+use uv_cache::Cache;
+use uv_client::Connectivity;
+use uv_configuration::{Concurrency, PreviewMode};
+use uv_normalize::PackageName;
+use uv_requirements::RequirementsSpecification;
+use uv_tool::{InstalledTools, Tool};
+use uv_warnings::warn_user_once;
+
+use crate::commands::project::update_environment;
+use crate::commands::{ExitStatus, SharedState};
+use crate::printer::Printer;
+use crate::settings::ResolverInstallerSettings;
+
+/// Remove a dependency from the environment of an installed tool.
+pub(crate) async fn uninject(
+    name: String,
+    with: Vec<String>,
+    settings: ResolverInstallerSettings,
+    preview: PreviewMode,
+    connectivity: Connectivity,
+    concurrency: Concurrency,
+    native_tls: bool,
+    cache: &Cache,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    if preview.is_disabled() {
+        warn_user_once!("`uv tool uninject` is experimental and may change without warning.");
+    }
+
+    let Ok(name) = PackageName::from_str(&name) else {
+        bail!("Invalid tool name: `{name}`")
+    };
+
+    let packages = with
+        .iter()
+        .map(|package| PackageName::from_str(package))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| anyhow::anyhow!("Invalid requirement name: {err}"))?;
+
+    for package in &packages {
+        if *package == name {
+            bail!(
+                "`{package}` is the target tool; use `uv tool uninstall {package}` to remove it entirely"
+            )
+        }
+    }
+
+    let installed_tools = InstalledTools::from_settings()?;
+    let Some(tool_receipt) = installed_tools.get_tool_receipt(&name)? else {
+        bail!("Tool `{name}` is not installed")
+    };
+    let Some(environment) = installed_tools.get_environment(&name, cache)? else {
+        bail!(
+            "Tool `{name}` is missing its environment; reinstall it with `uv tool install --force {name}`"
+        )
+    };
+
+    let mut requirements = tool_receipt
+        .requirements()
+        .iter()
+        .cloned()
+        .map(Requirement::from)
+        .collect::<Vec<_>>();
+
+    let before = requirements.len();
+    requirements.retain(|requirement| !packages.contains(&requirement.name));
+    if requirements.len() == before {
+        bail!(
+            "Tool `{name}` does not depend on {}",
+            packages.iter().join(", ")
+        );
+    }
+
+    let state = SharedState::default();
+    let spec = RequirementsSpecification::from_requirements(requirements.clone());
+
+    update_environment(
+        environment,
+        spec,
+        &settings,
+        &state,
+        preview,
+        connectivity,
+        concurrency,
+        native_tls,
+        cache,
+        printer,
+    )
+    .await?;
+
+    // Drop (and remove from disk) any entry point that came from one of the packages we just
+    // removed. These were only ever exposed because the now-uninjected dependency was present in
+    // the environment (e.g. via `--include-deps` at install time); leaving them in the receipt
+    // would point at scripts `update_environment` may have just uninstalled.
+    let mut remaining_entry_points = Vec::new();
+    for entry_point in tool_receipt.entrypoints() {
+        if packages.contains(entry_point.package()) {
+            debug!("Removing entry point `{}`", entry_point.name());
+            if entry_point.install_path().exists() {
+                fs_err::remove_file(entry_point.install_path())?;
+            }
+        } else {
+            remaining_entry_points.push(entry_point.clone());
+        }
+    }
+
+    let installed_tools = installed_tools.init()?;
+    let tool = Tool::new(
+        requirements
+            .into_iter()
+            .map(pep508_rs::Requirement::from)
+            .collect(),
+        tool_receipt.python().map(ToString::to_string),
+        remaining_entry_points,
+    );
+    installed_tools.add_tool_receipt(&name, tool)?;
+
+    writeln!(printer.stderr(), "Removed from `{name}`: {}", packages.iter().join(", "))?;
+
+    Ok(ExitStatus::Success)
+}